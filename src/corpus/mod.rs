@@ -1,16 +1,75 @@
-use crate::inputs::Input;
+use crate::inputs::{BytesInput, Input};
 use crate::utils::Rand;
 use crate::Error;
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
+use notify::{watcher, DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use std::any::{Any, TypeId};
 use std::fmt::Debug;
+use std::fs;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
 
-pub trait TestcaseMetadata: Debug {}
+pub trait TestcaseMetadata: Debug + Any {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// A type-erased bag of per-testcase metadata, keyed by the concrete
+/// metadata type rather than a caller-chosen string, so schedulers and
+/// feedbacks can each attach their own bookkeeping (execution count,
+/// depth, favored flag, ...) without colliding on a shared key. Each
+/// entry keeps its type name alongside the `TypeId` key so `Debug`
+/// printing still shows a human-readable label instead of an opaque
+/// hash; use `metadata`/`metadata_mut`/`add_metadata` to actually read
+/// and write entries.
+#[derive(Default)]
+pub struct MetadataMap {
+    entries: HashMap<TypeId, (&'static str, Box<dyn TestcaseMetadata>)>,
+}
+
+impl MetadataMap {
+    /// Attaches `meta`, replacing any previous value of the same type.
+    pub fn add_metadata<M: TestcaseMetadata>(&mut self, meta: M) {
+        self.entries
+            .insert(TypeId::of::<M>(), (std::any::type_name::<M>(), Box::new(meta)));
+    }
+
+    /// Looks up the metadata of type `M`, if any was attached.
+    pub fn metadata<M: TestcaseMetadata>(&self) -> Option<&M> {
+        self.entries
+            .get(&TypeId::of::<M>())
+            .and_then(|(_, meta)| meta.as_any().downcast_ref::<M>())
+    }
+
+    /// Mutably looks up the metadata of type `M`, if any was attached.
+    pub fn metadata_mut<M: TestcaseMetadata>(&mut self) -> Option<&mut M> {
+        self.entries
+            .get_mut(&TypeId::of::<M>())
+            .and_then(|(_, meta)| meta.as_any_mut().downcast_mut::<M>())
+    }
+}
+
+impl Debug for MetadataMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.entries.values().map(|(name, meta)| (name, meta)))
+            .finish()
+    }
+}
 
 pub trait Testcase: Debug {
     fn load_input(&mut self) -> Result<&Box<dyn Input>, Error>;
     fn is_on_disk(&self) -> bool;
     fn get_filename(&self) -> &str;
-    fn get_metadatas(&mut self) -> &mut HashMap<String, Box<dyn TestcaseMetadata>>;
+    fn get_metadatas(&mut self) -> &mut MetadataMap;
+
+    /// Drops any in-memory-only state the testcase is holding, e.g. a
+    /// loaded input, so it can be reclaimed later. The default is a
+    /// no-op; only disk-backed testcases need to do anything here.
+    fn evict(&mut self) {}
 }
 
 /// Corpus with all current testcases
@@ -18,7 +77,11 @@ pub trait Corpus: Debug {
     /// Returns the number of elements
     fn count(&self) -> usize;
 
-    fn add(&mut self, entry: Box<dyn Testcase>);
+    /// Adds `entry` to the corpus. Fails if persisting or otherwise
+    /// registering the entry runs into an I/O error; callers running
+    /// unattended (e.g. a fuzzing loop) should log and skip rather than
+    /// panic on a transient failure.
+    fn add(&mut self, entry: Box<dyn Testcase>) -> Result<(), Error>;
 
     /// Removes an entry from the corpus, returning it if it was present.
     fn remove(&mut self, entry: &dyn Testcase) -> Option<Box<dyn Testcase>>;
@@ -43,8 +106,9 @@ impl Corpus for RandomCorpus<'_> {
         self.entries.len()
     }
 
-    fn add(&mut self, entry: Box<dyn Testcase>) {
+    fn add(&mut self, entry: Box<dyn Testcase>) -> Result<(), Error> {
         self.entries.push(entry);
+        Ok(())
     }
 
     /// Removes an entry from the corpus, returning it if it was present.
@@ -100,8 +164,8 @@ impl Corpus for QueueCorpus<'_> {
         self.random_corpus.count()
     }
 
-    fn add(&mut self, entry: Box<dyn Testcase>) {
-        self.random_corpus.add(entry);
+    fn add(&mut self, entry: Box<dyn Testcase>) -> Result<(), Error> {
+        self.random_corpus.add(entry)
     }
 
     /// Removes an entry from the corpus, returning it if it was present.
@@ -146,11 +210,293 @@ impl QueueCorpus<'_> {
     }
 }
 
+/// A `Testcase` whose bytes are persisted to a file under a corpus
+/// directory. The input is only materialized in memory on the first
+/// call to `load_input`, and can be dropped again with `evict`.
+#[derive(Debug)]
+struct OnDiskTestcase {
+    is_on_disk: bool,
+    filename: String,
+    dir_path: String,
+    input: Option<Box<dyn Input>>,
+    metadatas: MetadataMap,
+}
+
+impl Testcase for OnDiskTestcase {
+    fn load_input(&mut self) -> Result<&Box<dyn Input>, Error> {
+        if self.input.is_none() {
+            let mut file = File::open(self.path()).map_err(|_| Error::Unknown)?;
+            let mut bytes = vec![];
+            file.read_to_end(&mut bytes).map_err(|_| Error::Unknown)?;
+            self.input = Some(Box::new(BytesInput::new(bytes)));
+        }
+        self.is_on_disk = false;
+        Ok(self.input.as_ref().unwrap())
+    }
+
+    fn is_on_disk(&self) -> bool {
+        self.is_on_disk
+    }
+
+    fn get_filename(&self) -> &str {
+        &self.filename
+    }
+
+    fn get_metadatas(&mut self) -> &mut MetadataMap {
+        &mut self.metadatas
+    }
+
+    fn evict(&mut self) {
+        // The bytes are already on disk (every `OnDiskTestcase` is backed
+        // by the file at `path()`), so we can simply drop the in-memory
+        // copy to free it back up.
+        self.input = None;
+        self.is_on_disk = true;
+    }
+}
+
+impl OnDiskTestcase {
+    fn path(&self) -> PathBuf {
+        Path::new(&self.dir_path).join(&self.filename)
+    }
+}
+
+/// A corpus that keeps only filenames and metadata in memory, storing
+/// every testcase's bytes under `dir_path` on disk. Inputs are loaded
+/// back into memory lazily, on the first `load_input` call, and can be
+/// evicted again with `evict` to cap memory usage on large corpora.
+#[derive(Debug)]
+pub struct OnDiskCorpus<'a> {
+    rand: &'a mut dyn Rand,
+    entries: Vec<Box<dyn Testcase>>,
+    dir_path: String,
+}
+
+impl Corpus for OnDiskCorpus<'_> {
+    /// Returns the number of elements
+    fn count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn add(&mut self, mut entry: Box<dyn Testcase>) -> Result<(), Error> {
+        let filename = entry.get_filename().to_owned();
+        let bytes = entry.load_input()?.bytes().to_vec();
+        self.persist(&filename, &bytes)?;
+        self.entries.push(Box::new(OnDiskTestcase {
+            is_on_disk: true,
+            filename,
+            dir_path: self.dir_path.clone(),
+            input: None,
+            metadatas: std::mem::take(entry.get_metadatas()),
+        }));
+        Ok(())
+    }
+
+    /// Removes an entry from the corpus, returning it if it was present.
+    fn remove(&mut self, entry: &dyn Testcase) -> Option<Box<dyn Testcase>> {
+        let pos = self
+            .entries
+            .iter()
+            .position(|x| x.as_ref() as *const _ == entry as *const _)?;
+        Some(self.entries.remove(pos))
+    }
+
+    /// Gets a random entry
+    fn random_entry(&mut self) -> Result<&Box<dyn Testcase>, Error> {
+        let id = self.rand.below(self.entries.len() as u64) as usize;
+        Ok(self.entries.get_mut(id).unwrap())
+    }
+
+    /// Gets the next entry
+    fn get(&mut self) -> Result<&Box<dyn Testcase>, Error> {
+        self.random_entry()
+    }
+}
+
+impl OnDiskCorpus<'_> {
+    /// Creates a new `OnDiskCorpus` rooted at `dir_path`, creating the
+    /// directory if it doesn't exist yet and importing any regular file
+    /// already present in it as a testcase. Subdirectories and symlinks
+    /// (including dangling ones) are skipped: `DirEntry::metadata` does
+    /// not follow symlinks, so only true regular files are picked up.
+    pub fn new<'a>(rand: &'a mut dyn Rand, dir_path: &str) -> Result<OnDiskCorpus<'a>, Error> {
+        fs::create_dir_all(dir_path).map_err(|_| Error::Unknown)?;
+
+        let mut entries = vec![];
+        for dir_entry in fs::read_dir(dir_path).map_err(|_| Error::Unknown)? {
+            let dir_entry = dir_entry.map_err(|_| Error::Unknown)?;
+            let metadata = dir_entry.metadata().map_err(|_| Error::Unknown)?;
+            if !metadata.is_file() {
+                continue;
+            }
+            entries.push(Box::new(OnDiskTestcase {
+                is_on_disk: true,
+                filename: dir_entry.file_name().to_string_lossy().into_owned(),
+                dir_path: dir_path.to_owned(),
+                input: None,
+                metadatas: MetadataMap::default(),
+            }) as Box<dyn Testcase>);
+        }
+
+        Ok(OnDiskCorpus {
+            rand,
+            entries,
+            dir_path: dir_path.to_owned(),
+        })
+    }
+
+    fn persist(&self, filename: &str, bytes: &[u8]) -> Result<(), Error> {
+        let path = Path::new(&self.dir_path).join(filename);
+        let mut file = File::create(path).map_err(|_| Error::Unknown)?;
+        file.write_all(bytes).map_err(|_| Error::Unknown)
+    }
+
+    /// Evicts every currently-loaded input back to disk, keeping only
+    /// the filename/metadata handle in memory. Call this between
+    /// fuzzing iterations to cap memory usage for large corpora.
+    pub fn evict_loaded(&mut self) {
+        for entry in &mut self.entries {
+            entry.evict();
+        }
+    }
+}
+
+/// Wraps a `Corpus` and keeps it synchronized with testcases written by
+/// other processes into the same `dir_path`, e.g. other fuzzer workers
+/// sharing a corpus directory. Create/modify events are debounced so a
+/// file that is still being written by another worker isn't read while
+/// half-complete. Call `poll`/`drain_new` between fuzzing iterations to
+/// ingest anything new; watching runs on the calling thread only, no
+/// locks, no background thread touching the inner corpus.
+///
+/// Uses the pre-5.0, `notify = "4"` debounced watcher API (the
+/// `notify::watcher` free function and `DebouncedEvent` enum); `notify`
+/// 5.x dropped debouncing into a separate crate, so bumping the
+/// dependency past 4.x needs this module ported to that crate too.
+#[derive(Debug)]
+pub struct SyncedCorpus<C: Corpus> {
+    inner: C,
+    dir_path: String,
+    watcher: RecommendedWatcher,
+    events: Receiver<DebouncedEvent>,
+    known: HashSet<String>,
+}
+
+impl<C: Corpus> SyncedCorpus<C> {
+    /// Wraps `inner`, watching `dir_path` recursively for new files.
+    /// Events are coalesced over `debounce` before being reported. Any
+    /// file already present in `dir_path` (e.g. from `inner`'s own
+    /// startup scan) is seeded into the dedup set up front, so a later
+    /// write to one of those pre-existing files doesn't get re-ingested
+    /// as a brand-new testcase.
+    pub fn new(inner: C, dir_path: &str, debounce: Duration) -> Result<Self, Error> {
+        let (tx, rx) = channel();
+        let mut watcher = watcher(tx, debounce).map_err(|_| Error::Unknown)?;
+        watcher
+            .watch(dir_path, RecursiveMode::Recursive)
+            .map_err(|_| Error::Unknown)?;
+
+        let mut known = HashSet::default();
+        for dir_entry in fs::read_dir(dir_path).map_err(|_| Error::Unknown)? {
+            let dir_entry = dir_entry.map_err(|_| Error::Unknown)?;
+            known.insert(dir_entry.file_name().to_string_lossy().into_owned());
+        }
+
+        Ok(SyncedCorpus {
+            inner,
+            dir_path: dir_path.to_owned(),
+            watcher,
+            events: rx,
+            known,
+        })
+    }
+
+    /// Drains every settled filesystem event queued up so far, ingesting
+    /// each path we haven't seen yet as a new testcase in the inner
+    /// corpus. Returns how many testcases were ingested. Never blocks.
+    pub fn drain_new(&mut self) -> usize {
+        let mut ingested = 0;
+        while let Ok(event) = self.events.try_recv() {
+            let path = match event {
+                DebouncedEvent::Create(path) | DebouncedEvent::Write(path) => path,
+                _ => continue,
+            };
+            if !path.is_file() {
+                continue;
+            }
+            let filename = match path.file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => continue,
+            };
+            // Dedup by filename: a worker's own outputs are already in
+            // `known` from `add`, so they never get re-imported here.
+            if !self.known.insert(filename.clone()) {
+                continue;
+            }
+            // `dir_path` is the event's own parent, not `self.dir_path`:
+            // the watch is recursive, so a file created in a subdirectory
+            // must keep its real nested location, or loading it later
+            // looks in the wrong place and fails.
+            let dir_path = match path.parent() {
+                Some(parent) => parent.to_string_lossy().into_owned(),
+                None => continue,
+            };
+            let added = self.inner.add(Box::new(OnDiskTestcase {
+                is_on_disk: true,
+                filename,
+                dir_path,
+                input: None,
+                metadatas: MetadataMap::default(),
+            }));
+            // Leave the filename in `known` even on failure: retrying
+            // every poll on a file we can't currently read would just
+            // spin, so we treat it the same as an already-seen entry.
+            if added.is_ok() {
+                ingested += 1;
+            }
+        }
+        ingested
+    }
+
+    /// Pumps the watcher between fuzzing iterations without the caller
+    /// needing to care how many testcases were picked up.
+    pub fn poll(&mut self) {
+        self.drain_new();
+    }
+}
+
+impl<C: Corpus> Corpus for SyncedCorpus<C> {
+    /// Returns the number of elements
+    fn count(&self) -> usize {
+        self.inner.count()
+    }
+
+    fn add(&mut self, entry: Box<dyn Testcase>) -> Result<(), Error> {
+        self.known.insert(entry.get_filename().to_owned());
+        self.inner.add(entry)
+    }
+
+    /// Removes an entry from the corpus, returning it if it was present.
+    fn remove(&mut self, entry: &dyn Testcase) -> Option<Box<dyn Testcase>> {
+        self.inner.remove(entry)
+    }
+
+    /// Gets a random entry
+    fn random_entry(&mut self) -> Result<&Box<dyn Testcase>, Error> {
+        self.inner.random_entry()
+    }
+
+    /// Gets the next entry
+    fn get(&mut self) -> Result<&Box<dyn Testcase>, Error> {
+        self.inner.get()
+    }
+}
+
 #[derive(Debug, Default)]
 struct SimpleTestcase {
     is_on_disk: bool,
     filename: String,
-    metadatas: HashMap<String, Box<dyn TestcaseMetadata>>,
+    metadatas: MetadataMap,
 }
 
 impl Testcase for SimpleTestcase {
@@ -167,7 +513,7 @@ impl Testcase for SimpleTestcase {
         &self.filename
     }
 
-    fn get_metadatas(&mut self) -> &mut HashMap<String, Box<dyn TestcaseMetadata>> {
+    fn get_metadatas(&mut self) -> &mut MetadataMap {
         &mut self.metadatas
     }
 }
@@ -177,7 +523,7 @@ impl SimpleTestcase {
         SimpleTestcase {
             filename: filename.to_owned(),
             is_on_disk: false,
-            metadatas: HashMap::default(),
+            metadatas: MetadataMap::default(),
         }
     }
 }
@@ -185,17 +531,270 @@ impl SimpleTestcase {
 #[cfg(test)]
 mod tests {
     use crate::corpus::Corpus;
+    use crate::corpus::OnDiskCorpus;
     use crate::corpus::QueueCorpus;
+    use crate::corpus::RandomCorpus;
     use crate::corpus::SimpleTestcase;
+    use crate::corpus::SyncedCorpus;
+    use crate::corpus::{MetadataMap, Testcase, TestcaseMetadata};
+    use crate::inputs::{BytesInput, Input};
     use crate::utils::Xoshiro256StarRand;
+    use std::any::Any;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    /// A testcase whose input already lives in memory, for tests that
+    /// need `load_input` to succeed (unlike `SimpleTestcase`, which
+    /// always returns `Error::Unknown`).
+    #[derive(Debug)]
+    struct InMemoryTestcase {
+        filename: String,
+        input: Box<dyn Input>,
+        metadatas: MetadataMap,
+    }
+
+    impl InMemoryTestcase {
+        fn new(filename: &str, bytes: Vec<u8>) -> Self {
+            InMemoryTestcase {
+                filename: filename.to_owned(),
+                input: Box::new(BytesInput::new(bytes)),
+                metadatas: MetadataMap::default(),
+            }
+        }
+    }
+
+    impl Testcase for InMemoryTestcase {
+        fn load_input(&mut self) -> Result<&Box<dyn Input>, crate::Error> {
+            Ok(&self.input)
+        }
+
+        fn is_on_disk(&self) -> bool {
+            false
+        }
+
+        fn get_filename(&self) -> &str {
+            &self.filename
+        }
+
+        fn get_metadatas(&mut self) -> &mut MetadataMap {
+            &mut self.metadatas
+        }
+    }
+
+    /// A fresh, unique scratch directory under the system temp dir for a
+    /// single test; the caller is responsible for cleaning it up.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "libafl_corpus_test_{name}_{}_{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
 
     #[test]
     fn test_queuecorpus() {
         let mut rand = Xoshiro256StarRand::new();
         let mut q = QueueCorpus::new(&mut rand, "fancy/path");
-        q.add(Box::new(SimpleTestcase::new("fancyfile")));
+        q.add(Box::new(SimpleTestcase::new("fancyfile"))).unwrap();
         let filename = q.get().unwrap().get_filename().to_owned();
         assert_eq!(filename, q.get().unwrap().get_filename());
         assert_eq!(filename, "fancyfile");
     }
+
+    #[derive(Debug, PartialEq)]
+    struct ExecCount(u64);
+
+    impl TestcaseMetadata for ExecCount {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Favored;
+
+    impl TestcaseMetadata for Favored {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_typed_metadata() {
+        let mut testcase = SimpleTestcase::new("fancyfile");
+        let metadatas = testcase.get_metadatas();
+
+        assert!(metadatas.metadata::<ExecCount>().is_none());
+
+        metadatas.add_metadata(ExecCount(1));
+        metadatas.add_metadata(Favored);
+        assert_eq!(metadatas.metadata::<ExecCount>(), Some(&ExecCount(1)));
+        assert_eq!(metadatas.metadata::<Favored>(), Some(&Favored));
+
+        metadatas.metadata_mut::<ExecCount>().unwrap().0 += 1;
+        assert_eq!(metadatas.metadata::<ExecCount>(), Some(&ExecCount(2)));
+    }
+
+    #[test]
+    fn test_metadata_map_debug_shows_type_names() {
+        let mut map = MetadataMap::default();
+        map.add_metadata(ExecCount(3));
+
+        let debug = format!("{:?}", map);
+        assert!(
+            debug.contains("ExecCount"),
+            "expected a human-readable type name in {debug:?}, not just a TypeId hash"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_ondiskcorpus_scan_is_regular_files_only() {
+        let dir = test_dir("scan_mutual_exclusivity");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("regular.bin"), b"testcase").unwrap();
+        fs::create_dir_all(dir.join("a_directory")).unwrap();
+        std::os::unix::fs::symlink(dir.join("does_not_exist"), dir.join("dangling_symlink"))
+            .unwrap();
+
+        let mut rand = Xoshiro256StarRand::new();
+        let corpus = OnDiskCorpus::new(&mut rand, dir.to_str().unwrap()).unwrap();
+
+        assert_eq!(corpus.count(), 1);
+        assert_eq!(corpus.entries[0].get_filename(), "regular.bin");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_ondiskcorpus_add_persists_and_evict_reloads() {
+        let dir = test_dir("evict_reload");
+        let mut rand = Xoshiro256StarRand::new();
+        let mut corpus = OnDiskCorpus::new(&mut rand, dir.to_str().unwrap()).unwrap();
+
+        corpus
+            .add(Box::new(InMemoryTestcase::new(
+                "fancyfile",
+                b"some fuzzy bytes".to_vec(),
+            )))
+            .unwrap();
+
+        // `add` persists to disk and keeps only the filename/metadata
+        // handle in memory.
+        assert!(corpus.entries[0].is_on_disk());
+        assert_eq!(
+            fs::read(dir.join("fancyfile")).unwrap(),
+            b"some fuzzy bytes"
+        );
+
+        // Loading materializes the input and flips `is_on_disk` off...
+        let loaded = corpus.entries[0].load_input().unwrap().bytes().to_vec();
+        assert_eq!(loaded, b"some fuzzy bytes");
+        assert!(!corpus.entries[0].is_on_disk());
+
+        // ...and evicting drops it again without losing the bytes on
+        // disk, so a later load still round-trips correctly.
+        corpus.evict_loaded();
+        assert!(corpus.entries[0].is_on_disk());
+        let reloaded = corpus.entries[0].load_input().unwrap().bytes().to_vec();
+        assert_eq!(reloaded, b"some fuzzy bytes");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_syncedcorpus_seeds_known_from_existing_files() {
+        let dir = test_dir("synced_seed");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("existing.bin"), b"x").unwrap();
+
+        let mut rand = Xoshiro256StarRand::new();
+        let inner = RandomCorpus::new(&mut rand, dir.to_str().unwrap());
+        let synced =
+            SyncedCorpus::new(inner, dir.to_str().unwrap(), Duration::from_millis(10)).unwrap();
+
+        assert!(synced.known.contains("existing.bin"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_syncedcorpus_add_marks_known_and_forwards_to_inner() {
+        let dir = test_dir("synced_add");
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut rand = Xoshiro256StarRand::new();
+        let inner = RandomCorpus::new(&mut rand, dir.to_str().unwrap());
+        let mut synced =
+            SyncedCorpus::new(inner, dir.to_str().unwrap(), Duration::from_millis(10)).unwrap();
+
+        synced
+            .add(Box::new(SimpleTestcase::new("own_output")))
+            .unwrap();
+
+        assert_eq!(synced.count(), 1);
+        assert!(synced.known.contains("own_output"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_syncedcorpus_drain_new_ingests_file_written_after_construction() {
+        let dir = test_dir("synced_drain");
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut rand = Xoshiro256StarRand::new();
+        let inner = RandomCorpus::new(&mut rand, dir.to_str().unwrap());
+        let debounce = Duration::from_millis(50);
+        let mut synced =
+            SyncedCorpus::new(inner, dir.to_str().unwrap(), debounce).unwrap();
+
+        fs::write(dir.join("new_from_worker.bin"), b"fuzzy bytes").unwrap();
+        // Give the watcher time to notice and debounce the write event.
+        std::thread::sleep(debounce * 4);
+
+        assert_eq!(synced.drain_new(), 1);
+        assert_eq!(synced.count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_syncedcorpus_drain_new_ingests_file_in_subdirectory() {
+        let dir = test_dir("synced_drain_nested");
+        fs::create_dir_all(&dir).unwrap();
+        let sub_dir = dir.join("worker_a");
+        fs::create_dir_all(&sub_dir).unwrap();
+
+        let mut rand = Xoshiro256StarRand::new();
+        let inner = OnDiskCorpus::new(&mut rand, dir.to_str().unwrap()).unwrap();
+        let debounce = Duration::from_millis(50);
+        let mut synced =
+            SyncedCorpus::new(inner, dir.to_str().unwrap(), debounce).unwrap();
+
+        fs::write(sub_dir.join("nested.bin"), b"nested fuzzy bytes").unwrap();
+        std::thread::sleep(debounce * 4);
+
+        assert_eq!(synced.drain_new(), 1);
+        // Ingesting must read the file from its real, nested path;
+        // `OnDiskCorpus::add` then copies those bytes to
+        // `dir_path/nested.bin`, which the stored entry loads from.
+        assert_eq!(
+            synced.inner.entries[0].load_input().unwrap().bytes().to_vec(),
+            b"nested fuzzy bytes"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,263 @@
+use crate::Error;
+use std::fmt::Debug;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How often we poll a running child for completion while waiting out
+/// the timeout.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+/// How long we give a process group to die after the first kill signal
+/// before following up with an unconditional kill.
+const KILL_GRACE_PERIOD: Duration = Duration::from_millis(200);
+
+/// Something that can run a target once and report how it went.
+pub trait Executor: Debug {
+    fn run_target(&mut self) -> Result<ExitKind, Error>;
+}
+
+/// The outcome of one run of the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitKind {
+    /// The target ran to completion without crashing or timing out.
+    Ok,
+    /// The target terminated abnormally (signal, or non-zero/unexpected
+    /// exit status).
+    Crash,
+    /// The target didn't finish within the configured timeout and the
+    /// whole process group was killed.
+    Timeout,
+}
+
+/// Runs a target binary out-of-process. The child is spawned as the
+/// leader of its own process group so that a timeout can tear down the
+/// entire subtree, including any grandchildren the harness itself
+/// forks, instead of leaking them.
+#[derive(Debug)]
+pub struct CommandExecutor {
+    program: String,
+    args: Vec<String>,
+    timeout: Duration,
+    last_stdout: Vec<u8>,
+    last_stderr: Vec<u8>,
+}
+
+impl CommandExecutor {
+    pub fn new(program: &str, args: &[String], timeout: Duration) -> Self {
+        CommandExecutor {
+            program: program.to_owned(),
+            args: args.to_owned(),
+            timeout,
+            last_stdout: vec![],
+            last_stderr: vec![],
+        }
+    }
+
+    /// Stdout captured from the most recent run.
+    pub fn stdout(&self) -> &[u8] {
+        &self.last_stdout
+    }
+
+    /// Stderr captured from the most recent run.
+    pub fn stderr(&self) -> &[u8] {
+        &self.last_stderr
+    }
+}
+
+impl Executor for CommandExecutor {
+    fn run_target(&mut self) -> Result<ExitKind, Error> {
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = platform::spawn_grouped(&mut command)?;
+        let start = Instant::now();
+
+        // Drain stdout/stderr on background threads as the child runs,
+        // rather than only after it exits: if the target writes more
+        // than the OS pipe buffer holds, it blocks in `write()` until
+        // someone reads, which `try_wait()` can't tell apart from an
+        // actual hang and would get misreported as a timeout instead of
+        // a crash.
+        let stdout_reader = child.stdout.take().map(spawn_pipe_reader);
+        let stderr_reader = child.stderr.take().map(spawn_pipe_reader);
+
+        let exit_kind = loop {
+            if let Some(status) = child.try_wait().map_err(|_| Error::Unknown)? {
+                break platform::exit_kind_of(status);
+            }
+            if start.elapsed() >= self.timeout {
+                platform::kill_group(&child);
+                std::thread::sleep(KILL_GRACE_PERIOD);
+                platform::kill_group_force(&child);
+                let _ = child.wait();
+                break ExitKind::Timeout;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        };
+
+        self.last_stdout = stdout_reader.map_or(vec![], |handle| handle.join().unwrap_or_default());
+        self.last_stderr = stderr_reader.map_or(vec![], |handle| handle.join().unwrap_or_default());
+
+        Ok(exit_kind)
+    }
+}
+
+/// Spawns a thread that reads `pipe` to completion (or EOF/error),
+/// returning the collected bytes when joined.
+fn spawn_pipe_reader<R: Read + Send + 'static>(mut pipe: R) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = vec![];
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::ExitKind;
+    use crate::Error;
+    use std::os::unix::process::{CommandExt, ExitStatusExt};
+    use std::process::{Child, Command, ExitStatus};
+
+    /// Spawns `command` as the leader of a new session/process group
+    /// (`setsid`), so its pgid equals its pid and a timeout can signal
+    /// `-pgid` to reach the whole subtree.
+    pub(super) fn spawn_grouped(command: &mut Command) -> Result<Child, Error> {
+        unsafe {
+            command.pre_exec(|| {
+                libc::setsid();
+                Ok(())
+            });
+        }
+        command.spawn().map_err(|_| Error::Unknown)
+    }
+
+    /// Sends `SIGTERM` to the whole process group. The caller follows up
+    /// with `kill_group_force` after a grace period in case a member of
+    /// the group ignored `SIGTERM`.
+    pub(super) fn kill_group(child: &Child) {
+        unsafe {
+            libc::kill(-(child.id() as libc::pid_t), libc::SIGTERM);
+        }
+    }
+
+    /// Sends `SIGKILL` to the whole process group (not just the child's
+    /// own pid) so a grandchild that ignored `SIGTERM` is reaped too.
+    pub(super) fn kill_group_force(child: &Child) {
+        unsafe {
+            libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+        }
+    }
+
+    pub(super) fn exit_kind_of(status: ExitStatus) -> ExitKind {
+        if status.signal().is_some() || !status.success() {
+            ExitKind::Crash
+        } else {
+            ExitKind::Ok
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::ExitKind;
+    use crate::Error;
+    use std::ops::{Deref, DerefMut};
+    use std::os::windows::io::AsRawHandle;
+    use std::os::windows::process::CommandExt;
+    use std::process::{Child as StdChild, Command, ExitStatus};
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, TerminateJobObject,
+    };
+    use windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP;
+
+    /// A spawned child together with the job object it was assigned to,
+    /// so the job can be terminated later to tear down its whole subtree.
+    pub(super) struct Child {
+        child: StdChild,
+        job: HANDLE,
+    }
+
+    impl Deref for Child {
+        type Target = StdChild;
+        fn deref(&self) -> &StdChild {
+            &self.child
+        }
+    }
+
+    impl DerefMut for Child {
+        fn deref_mut(&mut self) -> &mut StdChild {
+            &mut self.child
+        }
+    }
+
+    impl Drop for Child {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.job);
+            }
+        }
+    }
+
+    /// Spawns `command` in a new process group and assigns it to a fresh
+    /// job object, so terminating the job tears down the whole subtree.
+    pub(super) fn spawn_grouped(command: &mut Command) -> Result<Child, Error> {
+        let child = command
+            .creation_flags(CREATE_NEW_PROCESS_GROUP)
+            .spawn()
+            .map_err(|_| Error::Unknown)?;
+
+        let job = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+        if job != 0 {
+            unsafe {
+                AssignProcessToJobObject(job, child.as_raw_handle() as HANDLE);
+            }
+        }
+
+        Ok(Child { child, job })
+    }
+
+    pub(super) fn kill_group(child: &Child) {
+        unsafe {
+            TerminateJobObject(child.job, 1);
+        }
+    }
+
+    /// Job objects have no separate "are you sure" signal: terminating
+    /// the job already kills every process in it unconditionally, so
+    /// this is the same call as `kill_group`.
+    pub(super) fn kill_group_force(child: &Child) {
+        kill_group(child);
+    }
+
+    pub(super) fn exit_kind_of(status: ExitStatus) -> ExitKind {
+        if status.success() {
+            ExitKind::Ok
+        } else {
+            ExitKind::Crash
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommandExecutor, Executor, ExitKind};
+    use std::time::Duration;
+
+    #[test]
+    fn test_command_executor_ok() {
+        let mut executor = CommandExecutor::new("true", &[], Duration::from_secs(5));
+        assert_eq!(executor.run_target().unwrap(), ExitKind::Ok);
+    }
+
+    #[test]
+    fn test_command_executor_timeout() {
+        let args = ["5".to_owned()];
+        let mut executor = CommandExecutor::new("sleep", &args, Duration::from_millis(50));
+        assert_eq!(executor.run_target().unwrap(), ExitKind::Timeout);
+    }
+}